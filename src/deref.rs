@@ -1,6 +1,9 @@
 use proc_macro::{Span, TokenStream};
 use quote::quote;
-use syn::{parse_macro_input, Data, DeriveInput, Error, Index, Member, Result, Type};
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, Error, Field, Fields, Index, Member, Result,
+    Type,
+};
 
 macro_rules! compile_err {
     ($msg: expr) => {{
@@ -44,22 +47,13 @@ pub fn derive_deref_impl(input: TokenStream, is_mut: bool) -> TokenStream {
 
 fn deref_member(ast: &DeriveInput) -> Result<(Member, Type)> {
     if let Data::Struct(data) = &ast.data {
-        if data.fields.len() > 1 {
-            return Err(compile_err!(
-                "cannot apply `derive_deref` on struct with multi fields."
-            ));
-        }
+        let (index, field) = select_deref_field(&data.fields)?;
 
-        let field = data
-            .fields
-            .iter()
-            .next()
-            .ok_or(compile_err!("cannot apply `derive_deref` on empty struct."))?;
         let member = field
             .ident
             .as_ref()
             .map(|named| Member::Named(named.clone()))
-            .unwrap_or_else(|| Member::Unnamed(Index::from(0)));
+            .unwrap_or_else(|| Member::Unnamed(Index::from(index)));
 
         Ok((member, field.ty.clone()))
     } else {
@@ -68,3 +62,38 @@ fn deref_member(ast: &DeriveInput) -> Result<(Member, Type)> {
         ))
     }
 }
+
+/// Picks the field a struct should deref to.
+///
+/// Single-field structs need no annotation. Structs with more than one field must mark
+/// exactly one field with `#[deref]`.
+fn select_deref_field(fields: &Fields) -> Result<(usize, &Field)> {
+    if fields.len() <= 1 {
+        return fields
+            .iter()
+            .next()
+            .map(|field| (0, field))
+            .ok_or(compile_err!("cannot apply `derive_deref` on empty struct."));
+    }
+
+    let mut marked = fields
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| field.attrs.iter().any(is_deref_attr));
+
+    let selected = marked.next().ok_or(compile_err!(
+        "struct with multiple fields requires exactly one field marked `#[deref]`."
+    ))?;
+
+    if marked.next().is_some() {
+        return Err(compile_err!(
+            "only one field may be marked `#[deref]`."
+        ));
+    }
+
+    Ok(selected)
+}
+
+fn is_deref_attr(attr: &Attribute) -> bool {
+    attr.path().is_ident("deref")
+}
@@ -0,0 +1,55 @@
+use proc_macro::Span;
+use quote::quote;
+use sha3::{Digest, Sha3_256};
+use syn::{FnArg, Ident, Pat, PatIdent, Signature};
+
+/// Canonicalizes a [`Signature`] for ABI comparison: the `&self`/`&mut self` receiver is
+/// dropped and every parameter identifier is anonymized, leaving only the parameter types
+/// and the return type in source order.
+///
+/// This lets two signatures that only differ in receiver presence or argument naming
+/// (harmless formatting noise) compare as identical.
+fn canonicalize_signature(sig: &Signature) -> Signature {
+    let mut canonical = sig.clone();
+    canonical.constness = None;
+    canonical.asyncness = None;
+    canonical.unsafety = None;
+    canonical.abi = None;
+    canonical.generics = Default::default();
+    canonical.variadic = None;
+    canonical.ident = Ident::new("_", Span::call_site().into());
+    canonical.inputs = canonical
+        .inputs
+        .into_iter()
+        .filter_map(|arg| match arg {
+            FnArg::Receiver(_) => None,
+            FnArg::Typed(mut typed) => {
+                typed.pat = Box::new(Pat::Ident(PatIdent {
+                    attrs: vec![],
+                    by_ref: None,
+                    mutability: None,
+                    ident: Ident::new("_", Span::call_site().into()),
+                    subpat: None,
+                }));
+                Some(FnArg::Typed(typed))
+            }
+        })
+        .collect();
+    canonical
+}
+
+/// Computes the SHA3-256 fingerprint of a [`Signature`], hex-encoded.
+///
+/// The signature is canonicalized first (see [`canonicalize_signature`]) so that the
+/// fingerprint only captures the parameter types and return type, not receiver presence
+/// or argument naming.
+pub(crate) fn fingerprint_signature(sig: &Signature) -> String {
+    let canonical = canonicalize_signature(sig);
+    let rendered = quote! { #canonical }.to_string();
+    hex_encode(&Sha3_256::digest(rendered.as_bytes()))
+}
+
+/// Hex-encodes a byte slice, lowercase with no separators.
+pub(crate) fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
@@ -2,6 +2,8 @@ extern crate proc_macro;
 extern crate syn;
 
 mod deref;
+mod fingerprint;
+mod manifest;
 mod meta_collect;
 mod meta_generate;
 
@@ -40,8 +42,24 @@ use syn::{parse_macro_input, ItemFn};
 ///
 /// ```
 ///
+/// ## Multi-field struct with `#[deref]`
+///
+/// ```
+/// use derive_deref::Deref;
+///
+/// #[derive(Deref)]
+/// struct Registered {
+///     #[deref]
+///     value: i32,
+///     version: u32,
+/// };
+///
+/// let foo = Registered { value: 42_i32, version: 1 };
+/// assert_eq!(42, *foo);
+/// ```
+///
 /// [`Deref`]: ::std::ops::Deref
-#[proc_macro_derive(Deref)]
+#[proc_macro_derive(Deref, attributes(deref))]
 pub fn derive_deref(input: TokenStream) -> TokenStream {
     deref::derive_deref_impl(input, false)
 }
@@ -81,7 +99,7 @@ pub fn derive_deref(input: TokenStream) -> TokenStream {
 ///
 /// [`DerefMut`]: ::std::ops::DerefMut
 /// [`Deref`]: ::std::ops::Deref
-#[proc_macro_derive(DerefMut)]
+#[proc_macro_derive(DerefMut, attributes(deref))]
 pub fn derive_deref_mut(input: TokenStream) -> TokenStream {
     deref::derive_deref_impl(input, true)
 }
@@ -0,0 +1,315 @@
+use nom::{
+    bytes::complete::{tag, take_while1},
+    character::complete::{char, line_ending, space0, space1},
+    combinator::{map_res, opt},
+    error::{Error as NomError, ErrorKind},
+    multi::{many0, separated_list0},
+    sequence::{delimited, terminated, tuple},
+    Err as NomErr, IResult,
+};
+use sha3::{Digest, Sha3_256};
+
+use crate::fingerprint::hex_encode;
+
+/// One registered command as recorded in `src/.autogen/commands.manifest`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ManifestEntry {
+    pub code: u8,
+    pub name: String,
+    pub params: Vec<String>,
+    pub fingerprint: String,
+}
+
+/// The full command surface contract: every registered command plus a digest over the
+/// sorted set, so a compiled bytecode module can be checked against the ABI it was built for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CommandManifest {
+    pub entries: Vec<ManifestEntry>,
+    pub digest: String,
+}
+
+impl ManifestEntry {
+    fn render(&self) -> String {
+        format!(
+            "{:#04x} {} ({}) {}",
+            self.code,
+            self.name,
+            self.params.join(" | "),
+            self.fingerprint
+        )
+    }
+}
+
+/// Renders manifest entries into the on-disk textual IDL, sorted by opcode and followed by
+/// the `!manifest <digest>` line that [`parse_manifest`] reads back as a [`CommandManifest`].
+pub(crate) fn render_manifest(entries: &[ManifestEntry]) -> String {
+    let mut sorted = entries.to_vec();
+    sorted.sort_by_key(|entry| entry.code);
+
+    let lines: Vec<String> = sorted.iter().map(ManifestEntry::render).collect();
+    let digest = manifest_digest(&lines);
+
+    let mut out = lines.join("\n");
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out.push_str(&format!("!manifest {digest}\n"));
+    out
+}
+
+fn manifest_digest(sorted_lines: &[String]) -> String {
+    let mut hasher = Sha3_256::new();
+    for line in sorted_lines {
+        hasher.update(line.as_bytes());
+        hasher.update(b"\n");
+    }
+    hex_encode(&hasher.finalize())
+}
+
+fn hex_byte(input: &str) -> IResult<&str, u8> {
+    map_res(
+        tuple((tag("0x"), take_while1(|c: char| c.is_ascii_hexdigit()))),
+        |(_, hex): (&str, &str)| u8::from_str_radix(hex, 16),
+    )(input)
+}
+
+fn ident(input: &str) -> IResult<&str, &str> {
+    take_while1(|c: char| c.is_alphanumeric() || c == '_')(input)
+}
+
+// Params are separated with `|` rather than `,`, since `,` already appears inside
+// generic and tuple types (e.g. `HashMap<String, u8>`) and would make the list ambiguous.
+//
+// A type can itself contain parens (e.g. a function pointer `fn(u8) -> u8`), so a top-level
+// `)` can't simply terminate the match: only a `)`/`|` at paren depth 0 ends the type.
+fn param_type(input: &str) -> IResult<&str, String> {
+    let mut depth = 0i32;
+    let mut matched_len = 0usize;
+
+    for (i, c) in input.char_indices() {
+        if depth == 0 && (c == ')' || c == '|') {
+            break;
+        }
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => (),
+        }
+        matched_len = i + c.len_utf8();
+    }
+
+    if matched_len == 0 {
+        return Err(NomErr::Error(NomError::new(input, ErrorKind::TakeWhile1)));
+    }
+
+    let (matched, rest) = input.split_at(matched_len);
+    Ok((rest, matched.trim().to_string()))
+}
+
+fn params(input: &str) -> IResult<&str, Vec<String>> {
+    delimited(
+        char('('),
+        separated_list0(tuple((space0, char('|'), space0)), param_type),
+        char(')'),
+    )(input)
+}
+
+fn fingerprint(input: &str) -> IResult<&str, &str> {
+    take_while1(|c: char| c.is_ascii_hexdigit())(input)
+}
+
+fn entry(input: &str) -> IResult<&str, ManifestEntry> {
+    let (input, code) = hex_byte(input)?;
+    let (input, _) = space1(input)?;
+    let (input, name) = ident(input)?;
+    let (input, _) = space0(input)?;
+    let (input, params) = params(input)?;
+    let (input, _) = space1(input)?;
+    let (input, digest) = fingerprint(input)?;
+
+    Ok((
+        input,
+        ManifestEntry {
+            code,
+            name: name.to_string(),
+            params,
+            fingerprint: digest.to_string(),
+        },
+    ))
+}
+
+/// Parses a `commands.manifest` document, mirroring the line-oriented grammar it's
+/// rendered with: one `<opcode> <Name> (<param types>) <fingerprint>` line per command,
+/// followed by a `!manifest <digest>` line covering the sorted command set.
+pub(crate) fn parse_manifest(input: &str) -> IResult<&str, CommandManifest> {
+    let (input, entries) = many0(terminated(entry, line_ending))(input)?;
+    let (input, _) = tag("!manifest")(input)?;
+    let (input, _) = space1(input)?;
+    let (input, digest) = fingerprint(input)?;
+    let (input, _) = opt(line_ending)(input)?;
+
+    Ok((
+        input,
+        CommandManifest {
+            entries,
+            digest: digest.to_string(),
+        },
+    ))
+}
+
+/// Checks a freshly-assembled command set against the manifest it is about to replace on
+/// disk: if an opcode that was already claimed in `previous` is now bound to a different
+/// command name, bytecode compiled against the old manifest would silently misdispatch
+/// through the new one. Opcodes that are new, dropped, or unchanged are fine.
+pub(crate) fn validate_manifest_transition(
+    previous: &CommandManifest,
+    next: &[ManifestEntry],
+) -> Result<(), String> {
+    for old in &previous.entries {
+        if let Some(reassigned) = next
+            .iter()
+            .find(|entry| entry.code == old.code && entry.name != old.name)
+        {
+            return Err(format!(
+                "command code {:#04x} was bound to `{}` in the previous manifest, but is now claimed by `{}`; bytecode compiled against the old manifest would silently misdispatch",
+                old.code, old.name, reassigned.name
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Normalizes a type rendered via `ToTokens::to_string()` (e.g. `"HashMap < String , u8 >"`)
+/// into the compact form the manifest actually stores (e.g. `"HashMap<String, u8>"`), by
+/// dropping the spacing `quote!`/proc-macro2 insert around delimiter punctuation.
+pub(crate) fn normalize_param_type(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == ' ' {
+            let before_delim = matches!(chars.peek(), Some(',' | '>' | ')' | '<' | '('));
+            let after_opener = matches!(out.chars().last(), Some('(' | '<'));
+            if before_delim || after_opener {
+                continue;
+            }
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fake_fingerprint(tag: u8) -> String {
+        format!("{tag:02x}").repeat(32)
+    }
+
+    #[test]
+    fn render_then_parse_round_trips() {
+        let entries = vec![
+            ManifestEntry {
+                code: 0x00,
+                name: "Nope".to_string(),
+                params: vec![],
+                fingerprint: fake_fingerprint(0xaa),
+            },
+            ManifestEntry {
+                code: 0x01,
+                name: "MoveTo".to_string(),
+                params: vec!["f32".to_string(), "f32".to_string()],
+                fingerprint: fake_fingerprint(0xbb),
+            },
+        ];
+
+        let rendered = render_manifest(&entries);
+        let (rest, manifest) = parse_manifest(&rendered).unwrap();
+
+        assert!(rest.is_empty());
+        assert_eq!(manifest.entries.len(), entries.len());
+        assert_eq!(manifest.entries[0].code, 0x00);
+        assert_eq!(manifest.entries[0].params, Vec::<String>::new());
+        assert_eq!(manifest.entries[1].params, vec!["f32", "f32"]);
+        assert_eq!(manifest.digest.len(), 64);
+    }
+
+    #[test]
+    fn param_type_survives_nested_parens() {
+        let entries = vec![ManifestEntry {
+            code: 0x02,
+            name: "Callback".to_string(),
+            params: vec!["fn(u8) -> u8".to_string(), "HashMap<String, u8>".to_string()],
+            fingerprint: fake_fingerprint(0xcc),
+        }];
+
+        let rendered = render_manifest(&entries);
+        let (_, manifest) = parse_manifest(&rendered).unwrap();
+
+        assert_eq!(
+            manifest.entries[0].params,
+            vec!["fn(u8) -> u8", "HashMap<String, u8>"]
+        );
+    }
+
+    #[test]
+    fn validate_manifest_transition_allows_unchanged_and_new_codes() {
+        let previous = CommandManifest {
+            entries: vec![ManifestEntry {
+                code: 0x01,
+                name: "MoveTo".to_string(),
+                params: vec!["f32".to_string()],
+                fingerprint: fake_fingerprint(0xaa),
+            }],
+            digest: fake_fingerprint(0xff),
+        };
+        let next = vec![
+            ManifestEntry {
+                code: 0x01,
+                name: "MoveTo".to_string(),
+                params: vec!["f32".to_string(), "f32".to_string()],
+                fingerprint: fake_fingerprint(0xbb),
+            },
+            ManifestEntry {
+                code: 0x02,
+                name: "Jump".to_string(),
+                params: vec![],
+                fingerprint: fake_fingerprint(0xcc),
+            },
+        ];
+
+        assert!(validate_manifest_transition(&previous, &next).is_ok());
+    }
+
+    #[test]
+    fn validate_manifest_transition_rejects_opcode_reassignment() {
+        let previous = CommandManifest {
+            entries: vec![ManifestEntry {
+                code: 0x01,
+                name: "MoveTo".to_string(),
+                params: vec![],
+                fingerprint: fake_fingerprint(0xaa),
+            }],
+            digest: fake_fingerprint(0xff),
+        };
+        let next = vec![ManifestEntry {
+            code: 0x01,
+            name: "Jump".to_string(),
+            params: vec![],
+            fingerprint: fake_fingerprint(0xcc),
+        }];
+
+        let err = validate_manifest_transition(&previous, &next).unwrap_err();
+        assert!(err.contains("MoveTo"));
+        assert!(err.contains("Jump"));
+    }
+
+    #[test]
+    fn normalize_param_type_strips_quote_spacing() {
+        assert_eq!(
+            normalize_param_type("HashMap < String , u8 >"),
+            "HashMap<String, u8>"
+        );
+        assert_eq!(normalize_param_type("fn (u8) -> u8"), "fn(u8) -> u8");
+    }
+}
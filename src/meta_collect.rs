@@ -1,5 +1,4 @@
 use anyhow::Result as AnyResult;
-use base64::{engine::general_purpose, Engine as _};
 use convert_case::{Case, Casing};
 use if_chain::if_chain;
 use proc_macro::Span;
@@ -17,9 +16,12 @@ use syn::{
     parse,
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
-    Error as SynError, FnArg, Ident, ItemFn, Lit, Pat, Result, Token, Visibility,
+    Error as SynError, FnArg, ItemFn, Lit, Result, Token,
 };
 
+use crate::fingerprint::fingerprint_signature;
+use crate::meta_generate::WGSE_COMMANDS_DIR;
+
 macro_rules! compile_err {
     ($msg: expr) => {{
         SynError::new(Span::call_site().into(), $msg)
@@ -86,14 +88,14 @@ impl Parse for MetaCollectArgs {
 
 pub fn wgse_command_impl(args: MetaCollectArgs, ast: &mut ItemFn) -> AnyResult<()> {
     let file_name = format!(
-        "src/.autogen/wgse_command/{}.json",
+        "{WGSE_COMMANDS_DIR}/{}.json",
         args.name.to_case(Case::Snake)
     );
     let project_dir = env::current_dir()?;
     let file_path = Path::new(&project_dir).join(file_name);
 
-    let mut ast_clone = ast.clone();
-    check_function_interface(&mut ast_clone)?;
+    let fingerprint = fingerprint_signature(&ast.sig);
+    check_function_interface(&fingerprint)?;
 
     let receiver = quote! { &self }.into();
     ast.sig.inputs.insert(0, parse::<FnArg>(receiver)?);
@@ -101,51 +103,30 @@ pub fn wgse_command_impl(args: MetaCollectArgs, ast: &mut ItemFn) -> AnyResult<(
     let autogen_payload = json!({
         "name": args.name,
         "code": args.code,
-        "raw": quote!{ #ast }.to_string()
+        "fingerprint": fingerprint,
+        "raw": serde_json::from_str::<Value>(&syn_serde::json::to_string(ast))?
     });
     set_json_payload(&file_path, autogen_payload)?;
 
     Ok(())
 }
 
-fn preprocess_function_ast(ast: &mut ItemFn) -> AnyResult<()> {
-    let receiver = quote! { &self }.into();
-
-    ast.attrs = vec![];
-    ast.vis = Visibility::Inherited;
-    ast.sig.ident = Ident::new("_", Span::call_site().into());
-    ast.sig.inputs.insert(0, parse::<FnArg>(receiver)?);
-    ast.sig.inputs.iter_mut().for_each(|arg: &mut FnArg| {
-        if_chain! {
-            if let FnArg::Typed(arg) = arg;
-            if let Pat::Ident(ref mut ident) = *arg.pat;
-            then {
-                ident.ident = Ident::new("_", Span::call_site().into());
-            }
-        }
-    });
-    Ok(())
-}
-
-fn check_function_interface(ast: &mut ItemFn) -> AnyResult<()> {
-    preprocess_function_ast(ast)?;
-
+fn check_function_interface(func_fingerprint: &str) -> AnyResult<()> {
     let project_dir = env::current_dir()?;
     let interface_path = Path::new(&project_dir).join("src/.autogen/interface.json");
     let interface_payload = get_json_payload(&interface_path)?;
-    let interface_signature =
-        interface_payload["raw"]
+    let interface_fingerprint =
+        interface_payload["fingerprint"]
             .as_str()
             .ok_or(InvalidInterfaceError::new(
                 "no interface signature found. run `cargo build --features meta_init` once before run `cargo build --features meta_collect`.",
             ))?;
-    let func_signature = quote! { #(func.sig.clone()) }.to_string();
 
-    if interface_signature == func_signature {
+    if interface_fingerprint == func_fingerprint {
         Ok(())
     } else {
         Err(InvalidInterfaceError::new(&format!(
-            "inconsistent interface signature. expect `{interface_signature}`, found `{func_signature}`."
+            "inconsistent interface signature. expect fingerprint `{interface_fingerprint}`, found `{func_fingerprint}`."
         )))
     }?;
     Ok(())
@@ -155,17 +136,10 @@ fn get_json_payload(path: &Path) -> AnyResult<Value> {
     let mut content = String::new();
     BufReader::new(File::open(path)?).read_to_string(&mut content)?;
 
-    let mut json_value = serde_json::from_str::<Value>(&content)?;
-    json_value["raw"] = Value::String(String::from_utf8(
-        general_purpose::STANDARD.decode(json_value["raw"].as_str().unwrap())?,
-    )?);
-
-    Ok(json_value)
+    Ok(serde_json::from_str::<Value>(&content)?)
 }
 
-fn set_json_payload(path: &Path, mut json_value: Value) -> AnyResult<()> {
-    json_value["raw"] =
-        Value::String(general_purpose::STANDARD.encode(json_value["raw"].as_str().unwrap()));
+fn set_json_payload(path: &Path, json_value: Value) -> AnyResult<()> {
     BufWriter::new(File::create(path)?).write_all(json_value.to_string().as_bytes())?;
     Ok(())
 }
@@ -1,12 +1,14 @@
 use anyhow::Result as AnyResult;
-use base64::{engine::general_purpose, Engine as _};
 use convert_case::{Case, Casing};
 use if_chain::if_chain;
 use proc_macro::{Span, TokenStream};
-use quote::quote;
+use quote::{quote, ToTokens};
 use serde_json::{json, Value};
 use std::{
+    collections::{BTreeMap, HashMap},
     env,
+    error::Error,
+    fmt::Display,
     fs::File,
     io::{BufReader, BufWriter, Read, Write},
     path::Path,
@@ -14,6 +16,37 @@ use std::{
 use syn::{parse, parse_str, FnArg, Ident, ItemEnum, ItemFn, Pat, TraitItemFn, Visibility};
 use walkdir::WalkDir;
 
+use crate::fingerprint::fingerprint_signature;
+use crate::manifest::{
+    normalize_param_type, parse_manifest, render_manifest, validate_manifest_transition,
+    ManifestEntry,
+};
+
+const NOPE_COMMAND_NAME: &str = "Nope";
+const NOPE_COMMAND_CODE: u8 = 0x00;
+
+/// Directory `meta_collect::wgse_command_impl` writes each collected command's JSON payload
+/// into, and `parse_command_files` walks back out. Shared so the write and read sides can't
+/// drift apart again.
+pub(crate) const WGSE_COMMANDS_DIR: &str = "src/.autogen/wgse_commands";
+
+#[derive(Debug, Clone)]
+struct CommandCollisionError(String);
+
+impl CommandCollisionError {
+    fn new(msg: impl Into<String>) -> Self {
+        Self(msg.into())
+    }
+}
+
+impl Display for CommandCollisionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for CommandCollisionError {}
+
 pub fn wgse_command_interface_impl(_: TokenStream, input: TokenStream) -> AnyResult<TokenStream> {
     let project_name = env::current_dir()?;
     let file_path = project_name.join("src/.autogen/interface.json");
@@ -31,68 +64,170 @@ pub fn wgse_command_interface_impl(_: TokenStream, input: TokenStream) -> AnyRes
         }
     });
 
-    set_json_payload(&file_path, json! {{"raw": quote! {#func}.to_string()}})?;
+    let fingerprint = fingerprint_signature(&func.sig);
+
+    set_json_payload(
+        &file_path,
+        json! {{
+            "raw": serde_json::from_str::<Value>(&syn_serde::json::to_string(&func))?,
+            "fingerprint": fingerprint,
+        }},
+    )?;
     Ok(input)
 }
 
 pub fn wgse_command_trait_impl(arg: TokenStream, input: TokenStream) -> AnyResult<TokenStream> {
     let project_dir = env::current_dir()?;
-    let dest_dir = Path::new(&project_dir).join("src/.autogen/wgse_commands");
+    let dest_dir = Path::new(&project_dir).join(WGSE_COMMANDS_DIR);
 
     let trait_name = parse::<Ident>(arg)?;
+    // `target_enum` is the whole annotated item (`pub enum Cmd { ... }`), not just its name —
+    // only `enum_ident` is safe to interpolate into a position expecting an identifier.
     let target_enum = parse::<ItemEnum>(input)?;
+    let enum_ident = target_enum.ident;
+
+    let parsed = parse_command_files(&dest_dir, &trait_name)?;
 
-    let (mut commands_ast, tag_list) = parse_command_files(&dest_dir, &trait_name)?;
+    let manifest_path = Path::new(&project_dir).join("src/.autogen/commands.manifest");
+    write_manifest(&manifest_path, &parsed.manifest_entries)?;
 
-    let tags = tag_list.into_iter().map(|tag| quote! { #tag, });
+    let tags = parsed.tags.into_iter().map(|tag| quote! { #tag, });
+
+    let code_arms = parsed
+        .codes
+        .iter()
+        .map(|(tag, code)| quote! { #enum_ident::#tag(_) => #code, });
+    let from_code_arms = parsed
+        .codes
+        .iter()
+        .map(|(tag, code)| quote! { #code => #enum_ident::#tag(#tag), });
 
     // NOTE: the default command MUST be `.Nope`
     let variant_ast = Into::<TokenStream>::into(quote! {
         #[enum_dispatch(#trait_name)]
         #[derive(Debug, Clone, PartialEq, Eq)]
-        pub enum #target_enum
+        pub enum #enum_ident
         {
             #(#tags)*
         }
 
-        impl ::std::default::Default for #target_enum {
+        impl ::std::default::Default for #enum_ident {
             fn default() -> Self {
-                #target_enum::Nope(Nope)
+                #enum_ident::Nope(Nope)
+            }
+        }
+
+        impl #enum_ident {
+            /// Returns the opcode byte this command variant was registered under.
+            pub fn code(&self) -> u8 {
+                match self {
+                    #(#code_arms)*
+                }
+            }
+
+            /// Decodes an opcode byte into its default-initialized command variant,
+            /// defaulting any unrecognized opcode to [`Nope`].
+            pub fn from_code(code: u8) -> Self {
+                match code {
+                    #(#from_code_arms)*
+                    _ => #enum_ident::Nope(Nope),
+                }
             }
         }
     });
 
+    let mut commands_ast = parsed.ast;
     commands_ast.extend(vec![variant_ast]);
 
     Ok(commands_ast)
 }
 
-fn parse_command_files(
-    dest_dir: &Path,
-    trait_name: &Ident,
-) -> AnyResult<(TokenStream, Vec<Ident>)> {
+/// Everything `parse_command_files` extracts from the `.autogen/wgse_commands` tree: the
+/// generated `const`/tag-struct/trait-impl tokens, the variant idents and opcodes needed to
+/// build the dispatch enum, and the manifest entries describing the command surface.
+struct ParsedCommands {
+    ast: TokenStream,
+    tags: Vec<Ident>,
+    codes: Vec<(Ident, u8)>,
+    manifest_entries: Vec<ManifestEntry>,
+}
+
+fn parse_command_files(dest_dir: &Path, trait_name: &Ident) -> AnyResult<ParsedCommands> {
     let mut tag_list = vec![];
+    let mut tag_codes = vec![];
+    let mut manifest_entries = vec![];
     let mut ast = TokenStream::new();
 
+    // command code -> (command name, defining file), used to spot code collisions
+    let mut seen_codes: BTreeMap<u8, (String, String)> = BTreeMap::new();
+    // command name -> defining file, used to spot name collisions
+    let mut seen_names: HashMap<String, String> = HashMap::new();
+
     for entry in WalkDir::new(dest_dir)
         .into_iter()
         .filter_map(|path| path.ok())
         .filter(|path| path.file_type().is_file())
     {
+        let file_name = entry.path().display().to_string();
         let json_value = get_json_payload(entry.path())?;
 
         let name = json_value["name"]
             .as_str()
             .unwrap()
             .to_case(Case::UpperCamel);
-        let code = json_value["code"].as_u64().unwrap();
-        let mut func = parse_str::<ItemFn>(json_value["raw"].as_str().unwrap())?;
+        let code = json_value["code"].as_u64().unwrap() as u8;
+        let mut func: ItemFn = syn_serde::json::from_str(&json_value["raw"].to_string())?;
+
+        if code == NOPE_COMMAND_CODE && name != NOPE_COMMAND_NAME {
+            return Err(CommandCollisionError::new(format!(
+                "command code {code:#04x} is reserved for the mandatory default `{NOPE_COMMAND_NAME}` command, but `{name}` in `{file_name}` claims it"
+            ))
+            .into());
+        }
+
+        if let Some((other_name, other_file)) = seen_codes.get(&code) {
+            if other_name != &name {
+                return Err(CommandCollisionError::new(format!(
+                    "command code {code:#04x} is claimed by both `{other_name}` (in `{other_file}`) and `{name}` (in `{file_name}`)"
+                ))
+                .into());
+            }
+        }
+        seen_codes.insert(code, (name.clone(), file_name.clone()));
+
+        if let Some(other_file) = seen_names.insert(name.clone(), file_name.clone()) {
+            return Err(CommandCollisionError::new(format!(
+                "command name `{name}` is claimed by both `{other_file}` and `{file_name}`"
+            ))
+            .into());
+        }
 
         func.sig.ident = Ident::new("execute", Span::call_site().into());
         func.vis = Visibility::Inherited;
 
         // command name as variant member
-        tag_list.push(parse_str::<Ident>(&name)?);
+        let tag_ident = parse_str::<Ident>(&name)?;
+        tag_codes.push((tag_ident.clone(), code));
+        tag_list.push(tag_ident);
+
+        let fingerprint = json_value["fingerprint"].as_str().unwrap().to_string();
+        let params = func
+            .sig
+            .inputs
+            .iter()
+            .filter_map(|arg| match arg {
+                FnArg::Typed(typed) => Some(normalize_param_type(
+                    &typed.ty.to_token_stream().to_string(),
+                )),
+                FnArg::Receiver(_) => None,
+            })
+            .collect();
+        manifest_entries.push(ManifestEntry {
+            code,
+            name: name.clone(),
+            params,
+            fingerprint,
+        });
 
         // command code constant
         let const_name = parse_str::<Ident>(&name.to_case(Case::UpperSnake))?;
@@ -119,24 +254,45 @@ fn parse_command_files(
             vec![const_ast.into(), tag_name_ast.into(), impl_trait_ast.into()];
         ast.extend(command_ast);
     }
-    Ok((ast, tag_list))
+
+    if !seen_codes.contains_key(&NOPE_COMMAND_CODE) {
+        return Err(CommandCollisionError::new(format!(
+            "no command registers the mandatory default code {NOPE_COMMAND_CODE:#04x} (`{NOPE_COMMAND_NAME}`); `Default`, `code()`, and `from_code()` all require it to exist"
+        ))
+        .into());
+    }
+
+    Ok(ParsedCommands {
+        ast,
+        tags: tag_list,
+        codes: tag_codes,
+        manifest_entries,
+    })
+}
+
+/// Writes the rendered manifest to `path`, first checking it against whatever manifest is
+/// already there (if any) so an opcode can't silently be reassigned to a different command
+/// across builds.
+fn write_manifest(path: &Path, entries: &[ManifestEntry]) -> AnyResult<()> {
+    if let Ok(existing) = std::fs::read_to_string(path) {
+        if let Ok((_, previous)) = parse_manifest(&existing) {
+            validate_manifest_transition(&previous, entries)
+                .map_err(CommandCollisionError::new)?;
+        }
+    }
+
+    BufWriter::new(File::create(path)?).write_all(render_manifest(entries).as_bytes())?;
+    Ok(())
 }
 
 fn get_json_payload(path: &Path) -> AnyResult<Value> {
     let mut content = String::new();
     BufReader::new(File::open(path)?).read_to_string(&mut content)?;
 
-    let mut json_value = serde_json::from_str::<Value>(&content)?;
-    json_value["raw"] = Value::String(String::from_utf8(
-        general_purpose::STANDARD.decode(json_value["raw"].as_str().unwrap())?,
-    )?);
-
-    Ok(json_value)
+    Ok(serde_json::from_str::<Value>(&content)?)
 }
 
-fn set_json_payload(path: &Path, mut json_value: Value) -> AnyResult<()> {
-    json_value["raw"] =
-        Value::String(general_purpose::STANDARD.encode(json_value["raw"].as_str().unwrap()));
+fn set_json_payload(path: &Path, json_value: Value) -> AnyResult<()> {
     BufWriter::new(File::create(path)?).write_all(json_value.to_string().as_bytes())?;
     Ok(())
 }